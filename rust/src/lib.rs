@@ -1,14 +1,124 @@
+use postgres::{Client, NoTls, Row};
 use postgresql_embedded::blocking::PostgreSQL as BlockingPostgresql;
 use postgresql_embedded::Error::DatabaseInitializationError;
 use postgresql_embedded::Settings;
+use serde_json::{json, Value};
 use std::ffi::{CStr, CString};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
 use std::os::raw::c_char;
 use std::path::PathBuf;
 use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
 use std::time::Duration;
 
+/// Log levels passed to the callback registered via `pg_embedded_set_log_callback`.
+const LOG_LEVEL_INFO: i32 = 0;
+const LOG_LEVEL_WARN: i32 = 1;
+const LOG_LEVEL_ERROR: i32 = 2;
+
+type LogCallback = extern "C" fn(level: i32, message: *const c_char);
+
+static LOG_CALLBACK: Mutex<Option<LogCallback>> = Mutex::new(None);
+
+/// Registers a callback that receives every diagnostic line this library would otherwise write
+/// to stderr: setup/start/stop progress milestones, the `postgresql_embedded` failure text
+/// (which itself carries initdb/postgres error output) when setup or start fails, and — once a
+/// server is running — every line the running `postgres` process writes to its server log, via
+/// the background tailer started in `pg_embedded_create_and_start` (see `spawn_log_tailer`).
+/// Pass a null-equivalent by never calling this to keep the default stderr behavior.
+#[no_mangle]
+pub extern "C" fn pg_embedded_set_log_callback(cb: LogCallback) {
+    *LOG_CALLBACK.lock().unwrap() = Some(cb);
+}
+
+/// Routes a diagnostic line to the registered callback, falling back to stderr when none is set.
+fn log_message(level: i32, message: &str) {
+    let cb = *LOG_CALLBACK.lock().unwrap();
+    match cb {
+        Some(cb) => {
+            if let Ok(c_message) = CString::new(message) {
+                cb(level, c_message.as_ptr());
+            }
+        }
+        None => eprintln!("pg_embedded: {}", message),
+    }
+}
+
+/// Classifies one line of `postgresql_embedded`'s formatted setup/start error (which embeds
+/// whatever initdb/postgres output it captured) the same way pg-embed's command executor
+/// buffers and levels subprocess output, then forwards it to the log callback.
+fn log_line(line: &str) {
+    let level = if line.contains("FATAL") || line.contains("ERROR") {
+        LOG_LEVEL_ERROR
+    } else if line.contains("WARNING") || line.contains("WARN") {
+        LOG_LEVEL_WARN
+    } else {
+        LOG_LEVEL_INFO
+    };
+    log_message(level, line);
+}
+
+/// Tails a log file from its beginning, forwarding each new line through `log_line` until
+/// `stop` is set, with one last read after that so no line written before it is dropped.
+/// `postgresql_embedded` redirects the initdb/postgres child processes'
+/// stdout and stderr into `<data_dir>/postgresql.log`; the blocking API doesn't expose those
+/// pipes directly, so we poll the file it writes to instead.
+fn spawn_log_tailer(path: PathBuf, stop: Arc<AtomicBool>) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let mut position: u64 = 0;
+        let mut line = String::new();
+        loop {
+            let stopping = stop.load(Ordering::Relaxed);
+            if let Ok(mut file) = File::open(&path) {
+                if file.seek(SeekFrom::Start(position)).is_ok() {
+                    let mut reader = BufReader::new(file);
+                    loop {
+                        line.clear();
+                        match reader.read_line(&mut line) {
+                            Ok(0) | Err(_) => break,
+                            Ok(n) => {
+                                position += n as u64;
+                                log_line(line.trim_end_matches(['\n', '\r']));
+                            }
+                        }
+                    }
+                }
+            }
+            if stopping {
+                break;
+            }
+            thread::sleep(Duration::from_millis(200));
+        }
+    })
+}
+
+/// Owns the embedded PostgreSQL instance together with the background thread that tails its
+/// server log (see `spawn_log_tailer`) while the server is running. Derefs to the underlying
+/// `BlockingPostgresql` so existing call sites are unaffected by the wrapper.
+struct EmbeddedPgHandle {
+    pg: BlockingPostgresql,
+    tailer: Option<(Arc<AtomicBool>, thread::JoinHandle<()>)>,
+}
+
+impl std::ops::Deref for EmbeddedPgHandle {
+    type Target = BlockingPostgresql;
+
+    fn deref(&self) -> &BlockingPostgresql {
+        &self.pg
+    }
+}
+
+impl std::ops::DerefMut for EmbeddedPgHandle {
+    fn deref_mut(&mut self) -> &mut BlockingPostgresql {
+        &mut self.pg
+    }
+}
+
 /// Opaque type representing the embedded PostgreSQL instance.
-type EmbeddedPg = BlockingPostgresql;
+type EmbeddedPg = EmbeddedPgHandle;
 
 /// Result structure for pg_embedded_create_and_start.
 #[repr(C)]
@@ -19,6 +129,103 @@ pub struct pgStartResult {
 
 type PgStartResult = pgStartResult;
 
+/// Structured SQLSTATE error information, mirroring the classic Postgres error fields so Go
+/// callers can branch on `sqlstate_code` instead of string-matching `message`.
+///
+/// `position` is 1-based into the query text, or -1 when the server did not report one. Every
+/// `*mut c_char` field is either null or an owned string that must be released via
+/// `pg_embedded_free_error`.
+#[repr(C)]
+pub struct pgError {
+    severity: *mut c_char,
+    sqlstate_code: *mut c_char,
+    message: *mut c_char,
+    detail: *mut c_char,
+    hint: *mut c_char,
+    position: i32,
+}
+
+impl pgError {
+    fn empty() -> Self {
+        pgError {
+            severity: ptr::null_mut(),
+            sqlstate_code: ptr::null_mut(),
+            message: ptr::null_mut(),
+            detail: ptr::null_mut(),
+            hint: ptr::null_mut(),
+            position: -1,
+        }
+    }
+
+    /// Builds a `pgError` from a plain message, used when the failure did not originate from a
+    /// server-side SQLSTATE (e.g. a connection or I/O error).
+    fn from_message(severity: &str, message: String) -> Self {
+        pgError {
+            severity: string_to_c_char_ptr(severity.to_string()),
+            sqlstate_code: ptr::null_mut(),
+            message: string_to_c_char_ptr(message),
+            detail: ptr::null_mut(),
+            hint: ptr::null_mut(),
+            position: -1,
+        }
+    }
+
+    /// Builds a `pgError` from the classic Postgres `DbError` decomposition: severity, the
+    /// five-character SQLSTATE code, the primary message, and the optional detail/hint/position.
+    fn from_db_error(e: &postgres::error::DbError) -> Self {
+        let position = match e.position() {
+            Some(postgres::error::ErrorPosition::Original(p)) => *p as i32,
+            _ => -1,
+        };
+        pgError {
+            severity: string_to_c_char_ptr(e.severity().to_string()),
+            sqlstate_code: string_to_c_char_ptr(e.code().code().to_string()),
+            message: string_to_c_char_ptr(e.message().to_string()),
+            detail: e
+                .detail()
+                .map(|d| string_to_c_char_ptr(d.to_string()))
+                .unwrap_or(ptr::null_mut()),
+            hint: e
+                .hint()
+                .map(|h| string_to_c_char_ptr(h.to_string()))
+                .unwrap_or(ptr::null_mut()),
+            position,
+        }
+    }
+
+    fn from_postgres_error(e: &postgres::Error) -> Self {
+        match e.as_db_error() {
+            Some(db_err) => Self::from_db_error(db_err),
+            None => Self::from_message("ERROR", e.to_string()),
+        }
+    }
+}
+
+/// Writes `err` into `*err_out` if the caller passed a non-null pointer. No-op otherwise.
+fn set_error(err_out: *mut pgError, err: pgError) {
+    if !err_out.is_null() {
+        unsafe {
+            *err_out = err;
+        }
+    }
+}
+
+/// Frees the owned string fields inside a `pgError` populated by this library. Does not free
+/// the struct itself, which is owned by the caller.
+#[no_mangle]
+pub extern "C" fn pg_embedded_free_error(err: *mut pgError) {
+    if err.is_null() {
+        return;
+    }
+    let err = unsafe { &mut *err };
+    pg_embedded_free_string(err.severity);
+    pg_embedded_free_string(err.sqlstate_code);
+    pg_embedded_free_string(err.message);
+    pg_embedded_free_string(err.detail);
+    pg_embedded_free_string(err.hint);
+    *err = pgError::empty();
+}
+
 /// Helper to convert Rust String to C char pointer.
 /// The caller (C/Go) is responsible for freeing this string using `pg_embedded_free_string`.
 fn string_to_c_char_ptr(s: String) -> *mut c_char {
@@ -84,27 +291,40 @@ pub extern "C" fn pg_embedded_create_and_start(
 
     let mut pg = BlockingPostgresql::new(settings);
 
+    log_message(LOG_LEVEL_INFO, "setup: starting");
     if let Err(e) = pg.setup() {
         let error_str = match e {
             DatabaseInitializationError(reason) => format!("setup failed: {}", reason),
             _ => format!("setup failed: {}", e.to_string()),
         };
+        error_str.lines().for_each(log_line);
         return PgStartResult {
             pg_ptr: ptr::null_mut(),
             error_msg: string_to_c_char_ptr(error_str),
         };
     }
+    log_message(LOG_LEVEL_INFO, "setup: finished");
 
+    log_message(LOG_LEVEL_INFO, "start: starting");
     if let Err(e) = pg.start() {
         let error_str = format!("start failed: {}", e.to_string());
+        error_str.lines().for_each(log_line);
         return PgStartResult {
             pg_ptr: ptr::null_mut(),
             error_msg: string_to_c_char_ptr(error_str),
         };
     }
+    log_message(LOG_LEVEL_INFO, "start: finished");
+
+    let log_path = pg.settings().data_dir.join("postgresql.log");
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let tailer = spawn_log_tailer(log_path, Arc::clone(&stop_flag));
 
     PgStartResult {
-        pg_ptr: Box::into_raw(Box::new(pg)),
+        pg_ptr: Box::into_raw(Box::new(EmbeddedPgHandle {
+            pg,
+            tailer: Some((stop_flag, tailer)),
+        })),
         error_msg: ptr::null_mut(),
     }
 }
@@ -116,16 +336,129 @@ pub extern "C" fn pg_embedded_stop(pg_ptr: *mut EmbeddedPg) -> bool {
     }
     // Reconstitute the Box and let it drop, which calls `pg.stop()` if not already stopped
     // and handles cleanup via the Drop trait.
-    let pg = unsafe { Box::from_raw(pg_ptr) };
-    let result = pg.stop();
-    // pg is dropped when it goes out of scope here.
+    let mut handle = unsafe { Box::from_raw(pg_ptr) };
+    if let Some((stop_flag, tailer)) = handle.tailer.take() {
+        stop_flag.store(true, Ordering::Relaxed);
+        let _ = tailer.join();
+    }
+    log_message(LOG_LEVEL_INFO, "stop: stopping");
+    let result = handle.pg.stop();
+    // handle is dropped when it goes out of scope here.
+    if let Err(e) = &result {
+        log_message(LOG_LEVEL_ERROR, &format!("stop failed: {}", e));
+    } else {
+        log_message(LOG_LEVEL_INFO, "stop: finished");
+    }
     result.is_ok()
 }
 
+/// Percent-encodes a connection-string userinfo component (username or password), escaping
+/// every byte outside the URL unreserved set `A-Za-z0-9-._~` so values containing `@`, `:`,
+/// `/`, or `%` still produce a parseable `postgresql://` URL.
+fn percent_encode_userinfo(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+/// Quotes a value for use in a libpq keyword/value connection string (`key=value` pairs
+/// separated by whitespace), per the escaping rule libpq itself documents: wrap the value in
+/// single quotes and backslash-escape any single quote or backslash inside it. Always quoting
+/// is simplest and is valid even when the value contains no special characters.
+fn quote_conninfo_value(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('\'');
+    for c in s.chars() {
+        if c == '\\' || c == '\'' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('\'');
+    out
+}
+
+/// Splits a caller-supplied `host` or `host:port` override. Splits on the last `:`; the
+/// right-hand side is treated as the port only if it is entirely ASCII digits and fits in a
+/// `u16`, otherwise the whole string is treated as a bare host. An IPv6 address whose last
+/// group is all digits (such as `::1`) is therefore split as `host:port`.
+fn parse_host_override(s: &str) -> (String, Option<u16>) {
+    match s.rsplit_once(':') {
+        Some((host, port_str)) if !port_str.is_empty() && port_str.bytes().all(|b| b.is_ascii_digit()) => {
+            match port_str.parse::<u16>() {
+                Ok(port) => (host.to_string(), Some(port)),
+                Err(_) => (s.to_string(), None),
+            }
+        }
+        _ => (s.to_string(), None),
+    }
+}
+
+/// Builds the connection string used both by `pg_embedded_get_connection_string[_ex]` and by
+/// the SQL execution helpers below. `host_override` may be a bare host, a `host:port` pair, or
+/// an absolute path to a Unix socket directory, in which case a libpq keyword/value string
+/// (`host=... port=... dbname=...`) is emitted instead of a `postgresql://` URL.
+fn build_connection_string(pg: &EmbeddedPg, db_name: &str, host_override: Option<&str>) -> String {
+    let settings = pg.settings();
+    let user = if settings.username.is_empty() {
+        "postgres".to_string()
+    } else {
+        settings.username.clone() // Clone to get a String, or we can work with &str
+    };
+
+    let (host, port) = match host_override {
+        Some(h) if h.starts_with('/') => {
+            return format!(
+                "host={} port={} dbname={} user={} password={}",
+                quote_conninfo_value(h),
+                settings.port,
+                quote_conninfo_value(db_name),
+                quote_conninfo_value(&user),
+                quote_conninfo_value(&settings.password)
+            );
+        }
+        Some(h) => {
+            let (host, port) = parse_host_override(h);
+            (host, port.unwrap_or(settings.port))
+        }
+        None => ("localhost".to_string(), settings.port),
+    };
+
+    let userinfo_part = if !settings.password.is_empty() {
+        format!(
+            "{}:{}@",
+            percent_encode_userinfo(&user),
+            percent_encode_userinfo(&settings.password)
+        )
+    } else {
+        format!("{}@", percent_encode_userinfo(&user))
+    };
+
+    format!("postgresql://{}{}:{}/{}", userinfo_part, host, port, db_name)
+}
+
 #[no_mangle]
 pub extern "C" fn pg_embedded_get_connection_string(
     pg_ptr: *const EmbeddedPg,
     db_name_c: *const c_char,
+) -> *mut c_char {
+    pg_embedded_get_connection_string_ex(pg_ptr, db_name_c, ptr::null())
+}
+
+/// Like `pg_embedded_get_connection_string`, but lets the caller request a specific host or
+/// Unix-socket directory instead of the hardcoded `localhost`. Pass null to keep the default.
+#[no_mangle]
+pub extern "C" fn pg_embedded_get_connection_string_ex(
+    pg_ptr: *const EmbeddedPg,
+    db_name_c: *const c_char,
+    host_c: *const c_char,
 ) -> *mut c_char {
     if pg_ptr.is_null() {
         return std::ptr::null_mut();
@@ -133,35 +466,400 @@ pub extern "C" fn pg_embedded_get_connection_string(
     let pg = unsafe { &*pg_ptr };
     let db_name =
         unsafe { c_char_ptr_to_string(db_name_c).unwrap_or_else(|_| "postgres".to_string()) };
-
-    let settings = pg.settings();
-    let user = if settings.username.is_empty() {
-        "postgres".to_string()
+    let host_override = if host_c.is_null() {
+        None
     } else {
-        settings.username.clone() // Clone to get a String, or we can work with &str
+        unsafe { c_char_ptr_to_string(host_c) }.ok()
     };
-    let host = "localhost"; // postgresql-embedded runs on localhost
-    let port = settings.port;
 
-    let userinfo_part = if !settings.password.is_empty() {
-        // Note: Passwords with special characters might need URL encoding.
-        // This basic construction assumes simple passwords or that the Go driver handles it.
-        format!("{}:{}@", user, settings.password)
-    } else {
-        format!("{}@", user)
+    string_to_c_char_ptr(build_connection_string(pg, &db_name, host_override.as_deref()))
+}
+
+/// Opens a blocking client against the embedded instance, targeting `db_name`.
+fn connect(pg: &EmbeddedPg, db_name: &str) -> Result<Client, postgres::Error> {
+    let conn_str = build_connection_string(pg, db_name, None);
+    Client::connect(&conn_str, NoTls)
+}
+
+/// Renders a single column value from a result row as a JSON scalar or string; a column of a
+/// type not handled below becomes an `<unsupported column type: NAME>` marker string.
+fn row_value_to_json(row: &Row, idx: usize) -> Value {
+    let column = &row.columns()[idx];
+    match *column.type_() {
+        postgres::types::Type::BOOL => row
+            .try_get::<_, Option<bool>>(idx)
+            .ok()
+            .flatten()
+            .map(Value::Bool)
+            .unwrap_or(Value::Null),
+        postgres::types::Type::INT2 => row
+            .try_get::<_, Option<i16>>(idx)
+            .ok()
+            .flatten()
+            .map(|v| json!(v))
+            .unwrap_or(Value::Null),
+        postgres::types::Type::INT4 => row
+            .try_get::<_, Option<i32>>(idx)
+            .ok()
+            .flatten()
+            .map(|v| json!(v))
+            .unwrap_or(Value::Null),
+        postgres::types::Type::INT8 => row
+            .try_get::<_, Option<i64>>(idx)
+            .ok()
+            .flatten()
+            .map(|v| json!(v))
+            .unwrap_or(Value::Null),
+        postgres::types::Type::FLOAT4 => row
+            .try_get::<_, Option<f32>>(idx)
+            .ok()
+            .flatten()
+            .map(|v| json!(v))
+            .unwrap_or(Value::Null),
+        postgres::types::Type::FLOAT8 => row
+            .try_get::<_, Option<f64>>(idx)
+            .ok()
+            .flatten()
+            .map(|v| json!(v))
+            .unwrap_or(Value::Null),
+        postgres::types::Type::TEXT
+        | postgres::types::Type::VARCHAR
+        | postgres::types::Type::BPCHAR
+        | postgres::types::Type::NAME => row
+            .try_get::<_, Option<String>>(idx)
+            .ok()
+            .flatten()
+            .map(Value::String)
+            .unwrap_or(Value::Null),
+        postgres::types::Type::TIMESTAMPTZ => row
+            .try_get::<_, Option<chrono::DateTime<chrono::Utc>>>(idx)
+            .ok()
+            .flatten()
+            .map(|v| Value::String(v.to_rfc3339()))
+            .unwrap_or(Value::Null),
+        postgres::types::Type::DATE => row
+            .try_get::<_, Option<chrono::NaiveDate>>(idx)
+            .ok()
+            .flatten()
+            .map(|v| Value::String(v.to_string()))
+            .unwrap_or(Value::Null),
+        postgres::types::Type::TIME => row
+            .try_get::<_, Option<chrono::NaiveTime>>(idx)
+            .ok()
+            .flatten()
+            .map(|v| Value::String(v.to_string()))
+            .unwrap_or(Value::Null),
+        postgres::types::Type::NUMERIC => row
+            .try_get::<_, Option<rust_decimal::Decimal>>(idx)
+            .ok()
+            .flatten()
+            .map(|v| Value::String(v.to_string()))
+            .unwrap_or(Value::Null),
+        postgres::types::Type::UUID => row
+            .try_get::<_, Option<uuid::Uuid>>(idx)
+            .ok()
+            .flatten()
+            .map(|v| Value::String(v.to_string()))
+            .unwrap_or(Value::Null),
+        postgres::types::Type::JSON | postgres::types::Type::JSONB => row
+            .try_get::<_, Option<Value>>(idx)
+            .ok()
+            .flatten()
+            .unwrap_or(Value::Null),
+        // Anything we don't have a typed reader for (bytea, arrays, interval, custom/enum
+        // types, ...). Surfacing an explicit marker beats `try_get::<String>` silently
+        // swallowing a type-mismatch error into `null`.
+        ref other => Value::String(format!("<unsupported column type: {}>", other.name())),
+    }
+}
+
+/// Serializes a full result set to the `{"columns": [...], "rows": [[...], ...]}` shape shared
+/// by `pg_embedded_query` and `pg_embedded_query_params`. Column names come from the prepared
+/// statement's row description rather than the first row, so a query matching zero rows still
+/// reports its columns.
+fn rows_to_json(columns: &[postgres::Column], rows: &[Row]) -> Value {
+    let column_names: Vec<&str> = columns.iter().map(|c| c.name()).collect();
+
+    let values: Vec<Value> = rows
+        .iter()
+        .map(|row| {
+            Value::Array((0..row.len()).map(|i| row_value_to_json(row, i)).collect())
+        })
+        .collect();
+
+    json!({ "columns": column_names, "rows": values })
+}
+
+#[no_mangle]
+pub extern "C" fn pg_embedded_execute(
+    pg_ptr: *mut EmbeddedPg,
+    db_name_c: *const c_char,
+    sql_c: *const c_char,
+    err_out: *mut pgError,
+) -> *mut c_char {
+    if pg_ptr.is_null() {
+        return ptr::null_mut();
+    }
+    let pg = unsafe { &*pg_ptr };
+    let db_name = match unsafe { c_char_ptr_to_string(db_name_c) } {
+        Ok(s) => s,
+        Err(e) => {
+            set_error(err_out, pgError::from_message("ERROR", e.to_string()));
+            return ptr::null_mut();
+        }
+    };
+    let sql = match unsafe { c_char_ptr_to_string(sql_c) } {
+        Ok(s) => s,
+        Err(e) => {
+            set_error(err_out, pgError::from_message("ERROR", e.to_string()));
+            return ptr::null_mut();
+        }
     };
 
-    let conn_str = format!(
-        "postgresql://{}{}:{}/{}",
-        userinfo_part, host, port, db_name
-    );
-    string_to_c_char_ptr(conn_str)
+    let mut client = match connect(pg, &db_name) {
+        Ok(c) => c,
+        Err(e) => {
+            set_error(err_out, pgError::from_postgres_error(&e));
+            return ptr::null_mut();
+        }
+    };
+
+    match client.execute(&sql, &[]) {
+        Ok(rows_affected) => {
+            string_to_c_char_ptr(json!({ "rows_affected": rows_affected }).to_string())
+        }
+        Err(e) => {
+            set_error(err_out, pgError::from_postgres_error(&e));
+            ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn pg_embedded_query(
+    pg_ptr: *mut EmbeddedPg,
+    db_name_c: *const c_char,
+    sql_c: *const c_char,
+    err_out: *mut pgError,
+) -> *mut c_char {
+    if pg_ptr.is_null() {
+        return ptr::null_mut();
+    }
+    let pg = unsafe { &*pg_ptr };
+    let db_name = match unsafe { c_char_ptr_to_string(db_name_c) } {
+        Ok(s) => s,
+        Err(e) => {
+            set_error(err_out, pgError::from_message("ERROR", e.to_string()));
+            return ptr::null_mut();
+        }
+    };
+    let sql = match unsafe { c_char_ptr_to_string(sql_c) } {
+        Ok(s) => s,
+        Err(e) => {
+            set_error(err_out, pgError::from_message("ERROR", e.to_string()));
+            return ptr::null_mut();
+        }
+    };
+
+    let mut client = match connect(pg, &db_name) {
+        Ok(c) => c,
+        Err(e) => {
+            set_error(err_out, pgError::from_postgres_error(&e));
+            return ptr::null_mut();
+        }
+    };
+
+    let stmt = match client.prepare(&sql) {
+        Ok(s) => s,
+        Err(e) => {
+            set_error(err_out, pgError::from_postgres_error(&e));
+            return ptr::null_mut();
+        }
+    };
+
+    match client.query(&stmt, &[]) {
+        Ok(rows) => string_to_c_char_ptr(rows_to_json(stmt.columns(), &rows).to_string()),
+        Err(e) => {
+            set_error(err_out, pgError::from_postgres_error(&e));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Converts a single `{"type": "...", "value": ...}` bind parameter into the PostgreSQL type
+/// OID and boxed `ToSql` value `pg_embedded_query_params` binds via the extended query
+/// protocol (Parse/Bind/Execute) instead of inlining the value into the SQL text.
+fn parse_bind_param(
+    entry: &Value,
+) -> Result<(postgres::types::Type, Box<dyn postgres::types::ToSql + Sync>), String> {
+    let type_name = entry.get("type").and_then(Value::as_str).unwrap_or("text");
+    let value = entry.get("value").cloned().unwrap_or(Value::Null);
+
+    if type_name == "null" {
+        return Ok((postgres::types::Type::TEXT, Box::new(None::<String>)));
+    }
+
+    // Resolve the OID from `type_name` before looking at `value`, so a typed null (e.g.
+    // `{"type": "int4", "value": null}`) still binds with the OID the caller asked for instead
+    // of silently falling back to TEXT.
+    if value.is_null() {
+        let (ty, null_value): (postgres::types::Type, Box<dyn postgres::types::ToSql + Sync>) =
+            match type_name {
+                "int4" => (postgres::types::Type::INT4, Box::new(None::<i32>)),
+                "int8" => (postgres::types::Type::INT8, Box::new(None::<i64>)),
+                "float8" => (postgres::types::Type::FLOAT8, Box::new(None::<f64>)),
+                "bool" => (postgres::types::Type::BOOL, Box::new(None::<bool>)),
+                "timestamptz" => (
+                    postgres::types::Type::TIMESTAMPTZ,
+                    Box::new(None::<chrono::DateTime<chrono::Utc>>),
+                ),
+                _ => (postgres::types::Type::TEXT, Box::new(None::<String>)),
+            };
+        return Ok((ty, null_value));
+    }
+
+    match type_name {
+        "int4" => {
+            let n = value
+                .as_i64()
+                .ok_or_else(|| "expected an integer for type \"int4\"".to_string())?;
+            let n = i32::try_from(n)
+                .map_err(|_| format!("value {} out of range for type \"int4\"", n))?;
+            Ok((postgres::types::Type::INT4, Box::new(n)))
+        }
+        "int8" => {
+            let n = value
+                .as_i64()
+                .ok_or_else(|| "expected an integer for type \"int8\"".to_string())?;
+            Ok((postgres::types::Type::INT8, Box::new(n)))
+        }
+        "float8" => {
+            let n = value
+                .as_f64()
+                .ok_or_else(|| "expected a number for type \"float8\"".to_string())?;
+            Ok((postgres::types::Type::FLOAT8, Box::new(n)))
+        }
+        "bool" => {
+            let b = value
+                .as_bool()
+                .ok_or_else(|| "expected a boolean for type \"bool\"".to_string())?;
+            Ok((postgres::types::Type::BOOL, Box::new(b)))
+        }
+        "timestamptz" => {
+            let s = value
+                .as_str()
+                .ok_or_else(|| "expected a string for type \"timestamptz\"".to_string())?;
+            let ts = chrono::DateTime::parse_from_rfc3339(s)
+                .map_err(|e| format!("invalid timestamptz: {}", e))?
+                .with_timezone(&chrono::Utc);
+            Ok((postgres::types::Type::TIMESTAMPTZ, Box::new(ts)))
+        }
+        _ => {
+            let s = value
+                .as_str()
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| value.to_string());
+            Ok((postgres::types::Type::TEXT, Box::new(s)))
+        }
+    }
+}
+
+/// Like `pg_embedded_query`, but binds `params_json_c` (a JSON array of `{"type", "value"}`
+/// objects) as typed parameters through the extended query protocol instead of interpolating
+/// them into `sql_c`, giving Go callers injection-proof, correctly-typed substitution.
+#[no_mangle]
+pub extern "C" fn pg_embedded_query_params(
+    pg_ptr: *mut EmbeddedPg,
+    db_name_c: *const c_char,
+    sql_c: *const c_char,
+    params_json_c: *const c_char,
+    err_out: *mut pgError,
+) -> *mut c_char {
+    if pg_ptr.is_null() {
+        return ptr::null_mut();
+    }
+    let pg = unsafe { &*pg_ptr };
+    let db_name = match unsafe { c_char_ptr_to_string(db_name_c) } {
+        Ok(s) => s,
+        Err(e) => {
+            set_error(err_out, pgError::from_message("ERROR", e.to_string()));
+            return ptr::null_mut();
+        }
+    };
+    let sql = match unsafe { c_char_ptr_to_string(sql_c) } {
+        Ok(s) => s,
+        Err(e) => {
+            set_error(err_out, pgError::from_message("ERROR", e.to_string()));
+            return ptr::null_mut();
+        }
+    };
+    let params_json = match unsafe { c_char_ptr_to_string(params_json_c) } {
+        Ok(s) => s,
+        Err(e) => {
+            set_error(err_out, pgError::from_message("ERROR", e.to_string()));
+            return ptr::null_mut();
+        }
+    };
+
+    let entries: Vec<Value> = match serde_json::from_str(&params_json) {
+        Ok(v) => v,
+        Err(e) => {
+            set_error(
+                err_out,
+                pgError::from_message("ERROR", format!("invalid params_json: {}", e)),
+            );
+            return ptr::null_mut();
+        }
+    };
+
+    let mut types = Vec::with_capacity(entries.len());
+    let mut values: Vec<Box<dyn postgres::types::ToSql + Sync>> = Vec::with_capacity(entries.len());
+    for entry in &entries {
+        match parse_bind_param(entry) {
+            Ok((ty, val)) => {
+                types.push(ty);
+                values.push(val);
+            }
+            Err(msg) => {
+                set_error(err_out, pgError::from_message("ERROR", msg));
+                return ptr::null_mut();
+            }
+        }
+    }
+
+    let mut client = match connect(pg, &db_name) {
+        Ok(c) => c,
+        Err(e) => {
+            set_error(err_out, pgError::from_postgres_error(&e));
+            return ptr::null_mut();
+        }
+    };
+
+    let stmt = match client.prepare_typed(&sql, &types) {
+        Ok(s) => s,
+        Err(e) => {
+            set_error(err_out, pgError::from_postgres_error(&e));
+            return ptr::null_mut();
+        }
+    };
+
+    let param_refs: Vec<&(dyn postgres::types::ToSql + Sync)> =
+        values.iter().map(|v| v.as_ref()).collect();
+
+    match client.query(&stmt, &param_refs) {
+        Ok(rows) => string_to_c_char_ptr(rows_to_json(stmt.columns(), &rows).to_string()),
+        Err(e) => {
+            set_error(err_out, pgError::from_postgres_error(&e));
+            ptr::null_mut()
+        }
+    }
 }
 
 #[no_mangle]
 pub extern "C" fn pg_embedded_create_database(
     pg_ptr: *mut EmbeddedPg,
     db_name_c: *const c_char,
+    err_out: *mut pgError,
 ) -> bool {
     if pg_ptr.is_null() || db_name_c.is_null() {
         return false;
@@ -169,16 +867,30 @@ pub extern "C" fn pg_embedded_create_database(
     let pg = unsafe { &mut *pg_ptr };
     let db_name = match unsafe { c_char_ptr_to_string(db_name_c) } {
         Ok(s) if !s.is_empty() => s,
-        _ => return false,
+        Ok(_) => {
+            set_error(err_out, pgError::from_message("ERROR", "db_name must not be empty".to_string()));
+            return false;
+        }
+        Err(e) => {
+            set_error(err_out, pgError::from_message("ERROR", e.to_string()));
+            return false;
+        }
     };
 
-    pg.create_database(&db_name).is_ok()
+    match pg.create_database(&db_name) {
+        Ok(()) => true,
+        Err(e) => {
+            set_error(err_out, pgError::from_message("ERROR", e.to_string()));
+            false
+        }
+    }
 }
 
 #[no_mangle]
 pub extern "C" fn pg_embedded_drop_database(
     pg_ptr: *mut EmbeddedPg,
     db_name_c: *const c_char,
+    err_out: *mut pgError,
 ) -> bool {
     if pg_ptr.is_null() || db_name_c.is_null() {
         return false;
@@ -186,10 +898,23 @@ pub extern "C" fn pg_embedded_drop_database(
     let pg = unsafe { &mut *pg_ptr };
     let db_name = match unsafe { c_char_ptr_to_string(db_name_c) } {
         Ok(s) if !s.is_empty() => s,
-        _ => return false,
+        Ok(_) => {
+            set_error(err_out, pgError::from_message("ERROR", "db_name must not be empty".to_string()));
+            return false;
+        }
+        Err(e) => {
+            set_error(err_out, pgError::from_message("ERROR", e.to_string()));
+            return false;
+        }
     };
 
-    pg.drop_database(&db_name).is_ok()
+    match pg.drop_database(&db_name) {
+        Ok(()) => true,
+        Err(e) => {
+            set_error(err_out, pgError::from_message("ERROR", e.to_string()));
+            false
+        }
+    }
 }
 
 #[no_mangle]
@@ -209,6 +934,116 @@ pub extern "C" fn pg_embedded_database_exists(
     pg.database_exists(&db_name).unwrap_or(false)
 }
 
+/// Double-quotes a SQL identifier, escaping embedded quotes, for statements that can't use bind
+/// parameters (e.g. `CREATE DATABASE` naming a template).
+fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// Clones `template_db` into `new_db` via `CREATE DATABASE ... TEMPLATE ...`, letting test
+/// suites build a schema once and derive a cheap, isolated database per test from it.
+#[no_mangle]
+pub extern "C" fn pg_embedded_create_database_from_template(
+    pg_ptr: *mut EmbeddedPg,
+    new_db_c: *const c_char,
+    template_db_c: *const c_char,
+    err_out: *mut pgError,
+) -> bool {
+    if pg_ptr.is_null() {
+        return false;
+    }
+    let pg = unsafe { &*pg_ptr };
+    let new_db = match unsafe { c_char_ptr_to_string(new_db_c) } {
+        Ok(s) if !s.is_empty() => s,
+        Ok(_) => {
+            set_error(err_out, pgError::from_message("ERROR", "new_db_c must not be empty".to_string()));
+            return false;
+        }
+        Err(e) => {
+            set_error(err_out, pgError::from_message("ERROR", e.to_string()));
+            return false;
+        }
+    };
+    let template_db = match unsafe { c_char_ptr_to_string(template_db_c) } {
+        Ok(s) if !s.is_empty() => s,
+        Ok(_) => {
+            set_error(
+                err_out,
+                pgError::from_message("ERROR", "template_db_c must not be empty".to_string()),
+            );
+            return false;
+        }
+        Err(e) => {
+            set_error(err_out, pgError::from_message("ERROR", e.to_string()));
+            return false;
+        }
+    };
+
+    let mut client = match connect(pg, "postgres") {
+        Ok(c) => c,
+        Err(e) => {
+            set_error(err_out, pgError::from_postgres_error(&e));
+            return false;
+        }
+    };
+
+    let sql = format!(
+        "CREATE DATABASE {} TEMPLATE {}",
+        quote_ident(&new_db),
+        quote_ident(&template_db)
+    );
+    match client.execute(&sql, &[]) {
+        Ok(_) => true,
+        Err(e) => {
+            set_error(err_out, pgError::from_postgres_error(&e));
+            false
+        }
+    }
+}
+
+/// Reads `path_c` as a `.sql` schema file and runs it against `db_name_c` in one batch, the
+/// intended way to bootstrap a template database before cloning it with
+/// `pg_embedded_create_database_from_template`. Returns null on success, or an error JSON
+/// string (freed via `pg_embedded_free_string`) on failure.
+#[no_mangle]
+pub extern "C" fn pg_embedded_run_sql_file(
+    pg_ptr: *mut EmbeddedPg,
+    db_name_c: *const c_char,
+    path_c: *const c_char,
+) -> *mut c_char {
+    if pg_ptr.is_null() {
+        return string_to_c_char_ptr(json!({ "error": "pg_ptr is null" }).to_string());
+    }
+    let pg = unsafe { &*pg_ptr };
+    let db_name = match unsafe { c_char_ptr_to_string(db_name_c) } {
+        Ok(s) => s,
+        Err(e) => return string_to_c_char_ptr(json!({ "error": e.to_string() }).to_string()),
+    };
+    let path = match unsafe { c_char_ptr_to_string(path_c) } {
+        Ok(s) => s,
+        Err(e) => return string_to_c_char_ptr(json!({ "error": e.to_string() }).to_string()),
+    };
+
+    let sql = match std::fs::read_to_string(&path) {
+        Ok(s) => s,
+        Err(e) => {
+            return string_to_c_char_ptr(
+                json!({ "error": format!("failed to read {}: {}", path, e) }).to_string(),
+            )
+        }
+    };
+
+    let mut client = match connect(pg, &db_name) {
+        Ok(c) => c,
+        Err(e) => return string_to_c_char_ptr(json!({ "error": e.to_string() }).to_string()),
+    };
+
+    match client.batch_execute(&sql) {
+        Ok(()) => ptr::null_mut(),
+        Err(e) => string_to_c_char_ptr(json!({ "error": e.to_string() }).to_string()),
+    }
+}
+
 /// Frees a string that was allocated by Rust and passed to C.
 #[no_mangle]
 pub extern "C" fn pg_embedded_free_string(s: *mut c_char) {